@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use serde::Deserialize;
 use std::{fs, path::PathBuf};
 
@@ -7,6 +7,29 @@ pub struct Config {
     pub source: PathBuf,
     pub output: PathBuf,
     pub minify: bool,
+    pub mode: Mode,
+    pub highlight_theme: String,
+    pub taxonomies: Vec<String>,
+    pub taxonomy_template: PathBuf,
+    pub base_url: Option<String>,
+    pub generate_feed: bool,
+    pub generate_sitemap: bool,
+    pub feed_limit: usize,
+    pub browserslist: String,
+    pub css_source_maps: bool,
+    pub precompress: Vec<String>,
+}
+
+/// What the generator should do once the configuration is loaded.
+#[derive(Debug, Clone, Default)]
+pub enum Mode {
+    /// Walk `source` once and render everything into `output`.
+    #[default]
+    Build,
+
+    /// Build, then watch `source` and serve `output` over HTTP, rebuilding
+    /// on change and pushing a reload signal to connected browsers.
+    Serve { port: u16 },
 }
 
 impl Config {
@@ -45,13 +68,60 @@ struct Options {
     #[arg(skip)]
     minify: Option<bool>,
 
+    #[arg(long, value_name = "THEME")]
+    highlight_theme: Option<String>,
+
+    #[arg(skip)]
+    taxonomies: Option<Vec<String>>,
+
+    #[arg(skip)]
+    taxonomy_template: Option<PathBuf>,
+
+    #[arg(skip)]
+    base_url: Option<String>,
+
+    #[arg(skip)]
+    generate_feed: Option<bool>,
+
+    #[arg(skip)]
+    generate_sitemap: Option<bool>,
+
+    #[arg(skip)]
+    feed_limit: Option<usize>,
+
+    #[arg(long, value_name = "QUERY")]
+    browserslist: Option<String>,
+
+    #[arg(skip)]
+    css_source_maps: Option<bool>,
+
+    #[arg(skip)]
+    precompress: Option<Vec<String>>,
+
     #[serde(skip)]
     #[arg(short, long = "minify")]
     _minify_cli: Option<Option<bool>>,
 
+    #[serde(skip)]
+    #[arg(long = "source-maps")]
+    _css_source_maps_cli: Option<Option<bool>>,
+
     #[serde(skip)]
     #[arg(short, long, value_name = "FILE")]
     config: Option<PathBuf>,
+
+    #[serde(skip)]
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Clone, Debug)]
+enum Command {
+    /// Start a live-reloading development server
+    Serve {
+        #[arg(short, long, default_value_t = 3000)]
+        port: u16,
+    },
 }
 
 impl Options {
@@ -61,7 +131,11 @@ impl Options {
             Some(path) => Some(path.to_owned()),
             None => {
                 let path = PathBuf::from("neur.toml");
-                if path.try_exists()? { Some(path) } else { None }
+                if path.try_exists()? {
+                    Some(path)
+                } else {
+                    None
+                }
             }
         };
 
@@ -77,6 +151,20 @@ impl Options {
         self.source = self.source.or(rhs.source);
         self.output = self.output.or(rhs.output);
         self.minify = self._minify_cli.map(|o| o.unwrap_or(true)).or(rhs.minify);
+        self.highlight_theme = self.highlight_theme.or(rhs.highlight_theme);
+        self.taxonomies = self.taxonomies.or(rhs.taxonomies);
+        self.taxonomy_template = self.taxonomy_template.or(rhs.taxonomy_template);
+        self.base_url = self.base_url.or(rhs.base_url);
+        self.generate_feed = self.generate_feed.or(rhs.generate_feed);
+        self.generate_sitemap = self.generate_sitemap.or(rhs.generate_sitemap);
+        self.feed_limit = self.feed_limit.or(rhs.feed_limit);
+        self.browserslist = self.browserslist.or(rhs.browserslist);
+        self.css_source_maps = self
+            ._css_source_maps_cli
+            .map(|o| o.unwrap_or(true))
+            .or(rhs.css_source_maps);
+        self.precompress = self.precompress.or(rhs.precompress);
+        self.command = self.command.or(rhs.command);
         self
     }
 }
@@ -89,6 +177,24 @@ impl TryFrom<Options> for Config {
             source: opts.source.unwrap_or("src".into()),
             output: opts.output.unwrap_or("dist".into()),
             minify: opts.minify.unwrap_or(false),
+            highlight_theme: opts
+                .highlight_theme
+                .unwrap_or_else(|| "base16-ocean.dark".into()),
+            taxonomies: opts.taxonomies.unwrap_or_else(|| vec!["tags".into()]),
+            taxonomy_template: opts
+                .taxonomy_template
+                .unwrap_or_else(|| "_taxonomy.html".into()),
+            base_url: opts.base_url,
+            generate_feed: opts.generate_feed.unwrap_or(false),
+            generate_sitemap: opts.generate_sitemap.unwrap_or(false),
+            feed_limit: opts.feed_limit.unwrap_or(20),
+            browserslist: opts.browserslist.unwrap_or_else(|| "last 4 years".into()),
+            css_source_maps: opts.css_source_maps.unwrap_or(false),
+            precompress: opts.precompress.unwrap_or_default(),
+            mode: match opts.command {
+                Some(Command::Serve { port }) => Mode::Serve { port },
+                None => Mode::Build,
+            },
         };
 
         if cfg.output.starts_with(&cfg.source) {
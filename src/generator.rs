@@ -1,39 +1,264 @@
 use crate::Config;
+use comrak::nodes::{AstNode, NodeHtmlBlock, NodeValue};
 use lightningcss::{
     printer::PrinterOptions,
     stylesheet::StyleSheet,
     targets::{Browsers, Targets},
 };
+use notify::{RecursiveMode, Watcher};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt::{Debug, Display},
     fs,
+    io::{Read, Write},
     path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, SystemTime},
 };
+use syntect::{highlighting::Theme, html::highlighted_html_for_string, parsing::SyntaxSet};
 use tera::{Context, Tera};
 
 pub type Result<T> = std::result::Result<T, GeneratorError>;
 
+/// Injected into rendered pages while `serve()` is running so the browser
+/// can reconnect and refresh itself after a rebuild.
+const RELOAD_SCRIPT: &str =
+    "<script>new EventSource(\"/__neur_reload\").onmessage = () => location.reload();</script>";
+
 pub struct Generator {
     config: Config,
     tera: Tera,
     templates: Vec<PathBuf>,
+    serving: bool,
+    syntax_set: SyntaxSet,
+    highlight_theme: Theme,
+    taxonomies: HashMap<String, HashMap<String, Vec<PageEntry>>>,
+    pages: Vec<PageMeta>,
+}
+
+/// A single page's entry in a taxonomy term listing.
+#[derive(Clone, serde::Serialize)]
+struct PageEntry {
+    url: String,
+    title: Option<String>,
+    date: Option<String>,
+}
+
+/// One heading in a page's table of contents, nested under its nearest
+/// shallower ancestor heading.
+#[derive(Clone, serde::Serialize)]
+struct TocNode {
+    level: u8,
+    title: String,
+    id: String,
+    children: Vec<TocNode>,
+}
+
+/// Metadata for a rendered page, collected for the RSS/Atom feed and
+/// `sitemap.xml`.
+#[derive(Clone)]
+struct PageMeta {
+    source: PathBuf,
+    url: String,
+    title: Option<String>,
+    date: Option<String>,
+    description: Option<String>,
+    modified: SystemTime,
 }
 
 impl Generator {
     pub fn new(config: Config) -> Result<Self> {
         let source_glob = config.source.join("**/*");
 
+        let theme_set = syntect::highlighting::ThemeSet::load_defaults();
+        let highlight_theme = theme_set
+            .themes
+            .get(&config.highlight_theme)
+            .cloned()
+            .ok_or_else(|| GeneratorError::UnknownTheme(config.highlight_theme.clone()))?;
+
         Ok(Self {
-            config,
-            templates: Vec::new(),
             tera: Tera::new(source_glob.to_str().unwrap())?,
+            templates: Vec::new(),
+            serving: false,
+            syntax_set: SyntaxSet::load_defaults_newlines(),
+            highlight_theme,
+            taxonomies: HashMap::new(),
+            pages: Vec::new(),
+            config,
         })
     }
 
     pub fn run(&mut self) -> Result<()> {
         let source = self.config.source.clone();
-        self.directory(&source)
+        self.directory(&source)?;
+        self.render_taxonomies()?;
+        self.render_feed()?;
+        self.render_sitemap()
+    }
+
+    /// Build once, then watch `config.source` for changes, rebuilding
+    /// incrementally and serving `config.output` over HTTP with a live
+    /// reload script injected into every page.
+    pub fn serve(&mut self, port: u16) -> Result<()> {
+        self.serving = true;
+        self.run()?;
+
+        let server = tiny_http::Server::http(("127.0.0.1", port))
+            .map_err(|err| GeneratorError::Serve(err.to_string()))?;
+
+        println!(
+            "Serving {} at http://127.0.0.1:{port}",
+            self.config.output.display()
+        );
+
+        let clients: Arc<Mutex<Vec<Sender<()>>>> = Arc::new(Mutex::new(Vec::new()));
+        let http_clients = clients.clone();
+        let output = self.config.output.clone();
+
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let http_clients = http_clients.clone();
+                let output = output.clone();
+
+                thread::spawn(move || {
+                    if request.url() == "/__neur_reload" {
+                        Self::accept_reload_client(request, &http_clients);
+                    } else {
+                        Self::serve_static(&output, request);
+                    }
+                });
+            }
+        });
+
+        let (fs_tx, fs_rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(fs_tx)?;
+        watcher.watch(&self.config.source, RecursiveMode::Recursive)?;
+
+        self.watch(&fs_rx, &clients)
+    }
+
+    /// Block, debouncing filesystem events within a ~200ms window into a
+    /// single rebuild pass, then notify every connected browser to reload.
+    fn watch(
+        &mut self,
+        fs_rx: &mpsc::Receiver<notify::Result<notify::Event>>,
+        clients: &Arc<Mutex<Vec<Sender<()>>>>,
+    ) -> Result<()> {
+        loop {
+            let Ok(event) = fs_rx.recv() else {
+                return Ok(());
+            };
+
+            let mut changed = HashSet::new();
+            Self::collect_paths(event, &mut changed);
+
+            while let Ok(event) = fs_rx.recv_timeout(Duration::from_millis(200)) {
+                Self::collect_paths(event, &mut changed);
+            }
+
+            for path in &changed {
+                if let Err(err) = self.rebuild(path) {
+                    eprintln!("{err:?}");
+                }
+            }
+
+            if !changed.is_empty() {
+                if let Err(err) = self.render_taxonomies() {
+                    eprintln!("{err:?}");
+                }
+
+                clients.lock().unwrap().retain(|tx| tx.send(()).is_ok());
+            }
+        }
+    }
+
+    fn collect_paths(event: notify::Result<notify::Event>, changed: &mut HashSet<PathBuf>) {
+        match event {
+            Ok(event) => changed.extend(event.paths),
+            Err(err) => eprintln!("Watch error: {err}"),
+        }
+    }
+
+    /// Re-run the `file()` dispatch for a single changed path, the same way
+    /// `directory()` would during a full build. When a `_template.html`
+    /// changes, also rebuild every markdown file it templates.
+    fn rebuild(&mut self, path: &Path) -> Result<()> {
+        // The watcher reports absolute paths; map them back to the same
+        // cwd-relative form `directory()` walks, which `dest()` and
+        // `self.templates` rely on.
+        let cwd = std::env::current_dir()?;
+        let path = path.strip_prefix(&cwd).unwrap_or(path);
+
+        if !path.is_file() {
+            return Ok(());
+        }
+
+        // Templates are parsed once in `new()`; re-read them so pages
+        // rendered below pick up an edited template.
+        if path.extension().and_then(|ext| ext.to_str()) == Some("html") {
+            self.tera.full_reload()?;
+        }
+
+        fs::create_dir_all(self.dest(path).parent().unwrap())?;
+        self.file(path)?;
+
+        let is_template = path.file_name().and_then(|name| name.to_str()) == Some("_template.html");
+
+        if is_template {
+            self.rebuild_templated(path.parent().unwrap())?;
+        }
+
+        Ok(())
+    }
+
+    fn rebuild_templated(&mut self, dir: &Path) -> Result<()> {
+        if !self.templates.contains(&dir.to_path_buf()) {
+            return Ok(());
+        }
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+
+            if path.extension().and_then(|ext| ext.to_str()) == Some("md") {
+                self.markdown(&path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn accept_reload_client(request: tiny_http::Request, clients: &Arc<Mutex<Vec<Sender<()>>>>) {
+        let (tx, rx) = mpsc::channel();
+        clients.lock().unwrap().push(tx);
+
+        let header =
+            tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"text/event-stream"[..]).unwrap();
+        let response =
+            tiny_http::Response::new(200.into(), vec![header], ReloadStream(rx), None, None);
+
+        let _ = request.respond(response);
+    }
+
+    fn serve_static(root: &Path, request: tiny_http::Request) {
+        let mut path = root.join(request.url().trim_start_matches('/'));
+
+        if path.is_dir() {
+            path = path.join("index.html");
+        }
+
+        let response = match fs::read(&path) {
+            Ok(contents) => tiny_http::Response::from_data(contents).boxed(),
+            Err(_) => tiny_http::Response::from_string("404 Not Found")
+                .with_status_code(404)
+                .boxed(),
+        };
+
+        let _ = request.respond(response);
     }
 
     fn directory(&mut self, path: &Path) -> Result<()> {
@@ -65,6 +290,11 @@ impl Generator {
             "html" => self.html(path)?,
             "md" => markdown_paths.push(path.to_path_buf()),
 
+            _ if Self::is_compressible(extension) => {
+                let contents = fs::read(path)?;
+                self.write_output(&self.dest(path), &contents)?;
+            }
+
             _ => {
                 fs::copy(path, self.dest(path))?;
             }
@@ -86,22 +316,47 @@ impl Generator {
             .minify(Default::default())
             .map_err(|err| (path, err))?;
 
-        let output = styles
+        let dest = self.dest(path);
+
+        let browsers = Browsers::from_browserslist(std::iter::once(&self.config.browserslist))
+            .map_err(|err| GeneratorError::Browserslist(err.to_string()))?
+            .ok_or_else(|| {
+                GeneratorError::Browserslist(format!(
+                    "browserslist query \"{}\" matched no browsers",
+                    self.config.browserslist
+                ))
+            })?;
+
+        let mut output = styles
             .to_css(PrinterOptions {
                 minify: self.config.minify,
+                source_map: self.config.css_source_maps,
                 targets: Targets {
-                    browsers: Some(
-                        Browsers::from_browserslist(std::iter::once("last 4 years"))
-                            .unwrap()
-                            .unwrap(),
-                    ),
+                    browsers: Some(browsers),
                     ..Default::default()
                 },
                 ..Default::default()
             })
             .map_err(|err| (path, err))?;
 
-        fs::write(self.dest(path), output.code)?;
+        if let Some(source_map) = &mut output.map {
+            let map_path = dest.with_extension("css.map");
+            let map_json = source_map
+                .to_json(None)
+                .map_err(|err| GeneratorError::Css {
+                    file: path.to_path_buf(),
+                    err: err.to_string(),
+                })?;
+
+            output.code.push_str(&format!(
+                "\n/*# sourceMappingURL={} */\n",
+                map_path.file_name().unwrap().to_str().unwrap()
+            ));
+
+            fs::write(map_path, map_json)?;
+        }
+
+        self.write_output(&dest, output.code.as_bytes())?;
 
         Ok(())
     }
@@ -115,14 +370,16 @@ impl Generator {
             let rendered = self
                 .tera
                 .render(trimmed_path.to_str().unwrap(), &Context::new())?;
+            let rendered = self.with_reload_script(rendered);
+            let dest = self.dest(path);
+
+            self.record_page(path, &dest, None, None, None);
 
             if self.config.minify {
-                fs::write(
-                    self.dest(path),
-                    minify_html::minify(rendered.as_bytes(), &Default::default()),
-                )?;
+                let minified = minify_html::minify(rendered.as_bytes(), &Default::default());
+                self.write_output(&dest, &minified)?;
             } else {
-                fs::write(self.dest(path), rendered)?;
+                self.write_output(&dest, rendered.as_bytes())?;
             }
         } else if filename == "_template.html" {
             self.templates.push(path.parent().unwrap().into());
@@ -137,16 +394,34 @@ impl Generator {
 
         let mut options = comrak::Options::default();
         options.extension.front_matter_delimiter = Some("---".into());
+        options.extension.header_ids = Some(String::new());
 
-        context.try_insert("content", &comrak::markdown_to_html(&contents, &options))?;
+        let arena = comrak::Arena::new();
+        let root = comrak::parse_document(&arena, &contents, &options);
+        self.highlight_code_blocks(root);
+        context.try_insert("toc", &Self::extract_toc(root))?;
+
+        let mut content = Vec::new();
+        comrak::format_html(root, &options, &mut content)?;
+        context.try_insert("content", &String::from_utf8_lossy(&content))?;
 
         let (props, _): (HashMap<String, toml::Value>, _) =
             markdown_frontmatter::parse(&contents).map_err(|err| (path, err))?;
 
-        for (key, value) in props {
-            context.try_insert(key, &value)?;
+        for (key, value) in &props {
+            context.try_insert(key, value)?;
         }
 
+        let dest = self.dest(path).with_extension("html");
+        self.record_taxonomies(&dest, &props);
+        self.record_page(
+            path,
+            &dest,
+            props.get("title").and_then(toml::Value::as_str),
+            props.get("date").and_then(toml::Value::as_str),
+            props.get("description").and_then(toml::Value::as_str),
+        );
+
         let rendered = if self.templates.contains(&path.parent().unwrap().into()) {
             let trimmed_path = path
                 .parent()
@@ -164,16 +439,13 @@ impl Generator {
         } else {
             Tera::one_off(include_str!("default.html"), &context, false)?
         };
-
-        let dest = self.dest(path).with_extension("html");
+        let rendered = self.with_reload_script(rendered);
 
         if self.config.minify {
-            fs::write(
-                dest,
-                minify_html::minify(rendered.as_bytes(), &Default::default()),
-            )?;
+            let minified = minify_html::minify(rendered.as_bytes(), &Default::default());
+            self.write_output(&dest, &minified)?;
         } else {
-            fs::write(dest, rendered)?;
+            self.write_output(&dest, rendered.as_bytes())?;
         }
 
         Ok(())
@@ -184,6 +456,476 @@ impl Generator {
             .output
             .join(path.components().skip(1).collect::<PathBuf>())
     }
+
+    const COMPRESSIBLE_EXTENSIONS: &'static [&'static str] =
+        &["html", "css", "js", "mjs", "svg", "json"];
+
+    fn is_compressible(extension: &str) -> bool {
+        Self::COMPRESSIBLE_EXTENSIONS.contains(&extension)
+    }
+
+    /// Writes a rendered page or a copied text asset to disk. Copied
+    /// non-text files, `.css.map` files, `atom.xml` and `sitemap.xml` are
+    /// written directly, not through here. When `config.precompress` is set and the extension is allowlisted,
+    /// also writes `.gz`/`.br` siblings, but only when they end up smaller.
+    fn write_output(&self, dest: &Path, contents: &[u8]) -> Result<()> {
+        fs::write(dest, contents)?;
+
+        let extension = dest.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+
+        if self.config.precompress.is_empty() || !Self::is_compressible(extension) {
+            return Ok(());
+        }
+
+        for format in &self.config.precompress {
+            let (suffix, compressed) = match format.as_str() {
+                "gzip" => (".gz", Self::gzip(contents)?),
+                "brotli" => (".br", Self::brotli(contents)?),
+                _ => continue,
+            };
+
+            if compressed.len() < contents.len() {
+                fs::write(Self::sibling(dest, suffix), compressed)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn sibling(path: &Path, suffix: &str) -> PathBuf {
+        let mut name = path.as_os_str().to_owned();
+        name.push(suffix);
+        PathBuf::from(name)
+    }
+
+    fn gzip(contents: &[u8]) -> Result<Vec<u8>> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+
+        encoder
+            .write_all(contents)
+            .map_err(|err| GeneratorError::Compress(err.to_string()))?;
+
+        encoder
+            .finish()
+            .map_err(|err| GeneratorError::Compress(err.to_string()))
+    }
+
+    fn brotli(contents: &[u8]) -> Result<Vec<u8>> {
+        let mut output = Vec::new();
+        let params = brotli::enc::BrotliEncoderParams {
+            quality: 11,
+            ..Default::default()
+        };
+
+        brotli::BrotliCompress(&mut std::io::Cursor::new(contents), &mut output, &params)
+            .map_err(|err| GeneratorError::Compress(err.to_string()))?;
+
+        Ok(output)
+    }
+
+    fn url_for(&self, dest: &Path) -> String {
+        let relative = dest.strip_prefix(&self.config.output).unwrap_or(dest);
+        format!("/{}", relative.to_string_lossy().replace('\\', "/"))
+    }
+
+    /// Accumulate this page under every configured taxonomy it lists a term
+    /// for, e.g. `tags = ["rust", "web"]` in front matter. Drops any entries
+    /// this page left behind on a previous pass first, so rebuilding it
+    /// during `serve` (or removing a term from its front matter) doesn't
+    /// leave stale/duplicate listings around.
+    fn record_taxonomies(&mut self, dest: &Path, props: &HashMap<String, toml::Value>) {
+        let url = self.url_for(dest);
+
+        for terms in self.taxonomies.values_mut() {
+            for pages in terms.values_mut() {
+                pages.retain(|entry| entry.url != url);
+            }
+        }
+
+        let entry = PageEntry {
+            url,
+            title: props
+                .get("title")
+                .and_then(toml::Value::as_str)
+                .map(Into::into),
+            date: props
+                .get("date")
+                .and_then(toml::Value::as_str)
+                .map(Into::into),
+        };
+
+        for taxonomy in self.config.taxonomies.clone() {
+            let Some(terms) = props.get(&taxonomy).and_then(toml::Value::as_array) else {
+                continue;
+            };
+
+            for term in terms.iter().filter_map(toml::Value::as_str) {
+                self.taxonomies
+                    .entry(taxonomy.clone())
+                    .or_default()
+                    .entry(term.to_owned())
+                    .or_default()
+                    .push(entry.clone());
+            }
+        }
+    }
+
+    /// Phase two of the build: once every page has been walked and recorded
+    /// into `self.taxonomies`, emit a term listing for each term plus a
+    /// top-level listing of all terms, per taxonomy.
+    fn render_taxonomies(&mut self) -> Result<()> {
+        let template = self.config.taxonomy_template.to_str().unwrap().to_owned();
+
+        for (taxonomy, terms) in self.taxonomies.clone() {
+            let mut term_names: Vec<String> = terms.keys().cloned().collect();
+            term_names.sort();
+
+            for (term, mut pages) in terms {
+                pages.sort_by(|a, b| b.date.cmp(&a.date));
+
+                let mut context = Context::new();
+                context.try_insert("taxonomy", &taxonomy)?;
+                context.try_insert("term", &term)?;
+                context.try_insert("pages", &pages)?;
+
+                let rendered = self.with_reload_script(self.tera.render(&template, &context)?);
+                let dest = self
+                    .config
+                    .output
+                    .join(&taxonomy)
+                    .join(&term)
+                    .join("index.html");
+
+                fs::create_dir_all(dest.parent().unwrap())?;
+                self.write_output(&dest, rendered.as_bytes())?;
+            }
+
+            let mut context = Context::new();
+            context.try_insert("taxonomy", &taxonomy)?;
+            context.try_insert("terms", &term_names)?;
+
+            let rendered = self.with_reload_script(self.tera.render(&template, &context)?);
+            let dest = self.config.output.join(&taxonomy).join("index.html");
+
+            fs::create_dir_all(dest.parent().unwrap())?;
+            self.write_output(&dest, rendered.as_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    /// Record a page's metadata for the feed/sitemap passes, replacing any
+    /// entry previously recorded for the same source file so rebuilding it
+    /// during `serve` doesn't accumulate duplicates.
+    fn record_page(
+        &mut self,
+        source: &Path,
+        dest: &Path,
+        title: Option<&str>,
+        date: Option<&str>,
+        description: Option<&str>,
+    ) {
+        let modified = fs::metadata(source)
+            .and_then(|meta| meta.modified())
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+
+        self.pages.retain(|page| page.source != source);
+
+        self.pages.push(PageMeta {
+            source: source.to_path_buf(),
+            url: self.url_for(dest),
+            title: title.map(Into::into),
+            date: date.map(Into::into),
+            description: description.map(Into::into),
+            modified,
+        });
+    }
+
+    /// Serialize every recorded page into an Atom feed at `atom.xml`, most
+    /// recent first, capped to `config.feed_limit` entries.
+    fn render_feed(&self) -> Result<()> {
+        if !self.config.generate_feed {
+            return Ok(());
+        }
+
+        let base_url = self.config.base_url.as_deref().ok_or_else(|| {
+            GeneratorError::InvalidBaseUrl("base_url is required to generate a feed".into())
+        })?;
+
+        let mut pages = self.pages.clone();
+        pages.sort_by(|a, b| b.date.cmp(&a.date));
+        pages.truncate(self.config.feed_limit);
+
+        let mut entries = String::new();
+
+        for page in &pages {
+            let link = format!("{base_url}{}", page.url);
+            let title = page.title.as_deref().unwrap_or(&page.url);
+
+            entries.push_str("  <entry>\n");
+            entries.push_str(&format!("    <title>{}</title>\n", escape_xml(title)));
+            entries.push_str(&format!("    <link href=\"{}\"/>\n", escape_xml(&link)));
+            entries.push_str(&format!("    <id>{}</id>\n", escape_xml(&link)));
+
+            if let Some(date) = &page.date {
+                entries.push_str(&format!("    <updated>{}</updated>\n", escape_xml(date)));
+            }
+
+            if let Some(description) = &page.description {
+                entries.push_str(&format!(
+                    "    <summary>{}</summary>\n",
+                    escape_xml(description)
+                ));
+            }
+
+            entries.push_str("  </entry>\n");
+        }
+
+        let feed = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\">\n  <title>{}</title>\n  <id>{base_url}/</id>\n{entries}</feed>\n",
+            escape_xml(base_url),
+        );
+
+        fs::write(self.config.output.join("atom.xml"), feed)?;
+
+        Ok(())
+    }
+
+    /// Emit `sitemap.xml` listing every recorded page, falling back to the
+    /// source file's mtime for `<lastmod>` when no front-matter date exists.
+    fn render_sitemap(&self) -> Result<()> {
+        if !self.config.generate_sitemap {
+            return Ok(());
+        }
+
+        let base_url = self.config.base_url.as_deref().ok_or_else(|| {
+            GeneratorError::InvalidBaseUrl("base_url is required to generate a sitemap".into())
+        })?;
+
+        let mut urls = String::new();
+
+        for page in &self.pages {
+            let lastmod = match &page.date {
+                Some(date) => date.clone(),
+                None => humantime::format_rfc3339(page.modified).to_string(),
+            };
+
+            urls.push_str("  <url>\n");
+            urls.push_str(&format!(
+                "    <loc>{base_url}{}</loc>\n",
+                escape_xml(&page.url)
+            ));
+            urls.push_str(&format!(
+                "    <lastmod>{}</lastmod>\n",
+                escape_xml(&lastmod)
+            ));
+            urls.push_str("  </url>\n");
+        }
+
+        let sitemap = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n{urls}</urlset>\n"
+        );
+
+        fs::write(self.config.output.join("sitemap.xml"), sitemap)?;
+
+        Ok(())
+    }
+
+    /// Walk the parsed markdown AST for headings and fold the flat,
+    /// document-order list into a nested tree, using the same `Anchorizer`
+    /// comrak's `header_ids` extension uses so `id`s match the in-page
+    /// anchors it writes into the rendered HTML.
+    fn extract_toc<'a>(root: &'a AstNode<'a>) -> Vec<TocNode> {
+        let mut anchorizer = comrak::Anchorizer::new();
+        let mut headings = Vec::new();
+
+        Self::collect_headings(root, &mut anchorizer, &mut headings);
+        Self::nest_headings(headings)
+    }
+
+    fn collect_headings<'a>(
+        node: &'a AstNode<'a>,
+        anchorizer: &mut comrak::Anchorizer,
+        headings: &mut Vec<(u8, String, String)>,
+    ) {
+        for child in node.children() {
+            if let NodeValue::Heading(heading) = &child.data.borrow().value {
+                let title = Self::heading_text(child);
+                let id = anchorizer.anchorize(title.clone());
+                headings.push((heading.level, title, id));
+            }
+
+            Self::collect_headings(child, anchorizer, headings);
+        }
+    }
+
+    fn heading_text<'a>(node: &'a AstNode<'a>) -> String {
+        let mut text = String::new();
+
+        for child in node.children() {
+            match &child.data.borrow().value {
+                NodeValue::Text(value) => text.push_str(value),
+                NodeValue::Code(code) => text.push_str(&code.literal),
+                _ => {}
+            }
+
+            text.push_str(&Self::heading_text(child));
+        }
+
+        text
+    }
+
+    /// Fold a flat, document-order `(level, title, id)` list into a tree by
+    /// keeping a stack of still-open ancestors, closing (and attaching)
+    /// every heading at the same level or deeper before pushing the next
+    /// one. A heading attaches to the nearest shallower ancestor still on
+    /// the stack, so non-monotonic jumps (e.g. h1 then h3) degrade
+    /// gracefully instead of panicking or dropping nodes.
+    fn nest_headings(flat: Vec<(u8, String, String)>) -> Vec<TocNode> {
+        let mut stack: Vec<TocNode> = Vec::new();
+        let mut roots: Vec<TocNode> = Vec::new();
+
+        for (level, title, id) in flat {
+            while let Some(top) = stack.last() {
+                if top.level < level {
+                    break;
+                }
+
+                let finished = stack.pop().unwrap();
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(finished),
+                    None => roots.push(finished),
+                }
+            }
+
+            stack.push(TocNode {
+                level,
+                title,
+                id,
+                children: Vec::new(),
+            });
+        }
+
+        while let Some(finished) = stack.pop() {
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(finished),
+                None => roots.push(finished),
+            }
+        }
+
+        roots
+    }
+
+    /// Replace every fenced code block in the parsed markdown AST with a
+    /// pre-highlighted `syntect` HTML block, falling back to plain text
+    /// when the fence's language tag is unknown or absent.
+    fn highlight_code_blocks<'a>(&self, node: &'a AstNode<'a>) {
+        for child in node.children() {
+            let code_block = match &child.data.borrow().value {
+                NodeValue::CodeBlock(block) => Some((block.info.clone(), block.literal.clone())),
+                _ => None,
+            };
+
+            if let Some((info, literal)) = code_block {
+                let syntax = self
+                    .syntax_set
+                    .find_syntax_by_token(&info)
+                    .unwrap_or_else(|| self.syntax_set.find_syntax_plain_text());
+
+                let highlighted = highlighted_html_for_string(
+                    &literal,
+                    &self.syntax_set,
+                    syntax,
+                    &self.highlight_theme,
+                )
+                .unwrap_or(literal);
+
+                child.data.borrow_mut().value = NodeValue::HtmlBlock(NodeHtmlBlock {
+                    block_type: 6,
+                    literal: highlighted,
+                });
+            }
+
+            self.highlight_code_blocks(child);
+        }
+    }
+
+    fn with_reload_script(&self, rendered: String) -> String {
+        if self.serving {
+            format!("{rendered}{RELOAD_SCRIPT}")
+        } else {
+            rendered
+        }
+    }
+}
+
+/// A `Read` impl that blocks until a rebuild happens, then yields one SSE
+/// `data:` frame. Used as the body of the `/__neur_reload` long-lived
+/// response so `tiny_http` streams a frame out each time it is signalled.
+struct ReloadStream(mpsc::Receiver<()>);
+
+impl Read for ReloadStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.0.recv().is_err() {
+            return Ok(0);
+        }
+
+        let frame = b"data: reload\n\n";
+        let len = frame.len().min(buf.len());
+        buf[..len].copy_from_slice(&frame[..len]);
+
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heading(level: u8, title: &str) -> (u8, String, String) {
+        let id = title.to_lowercase().replace(' ', "-");
+        (level, title.into(), id)
+    }
+
+    #[test]
+    fn nests_sibling_headings_under_their_shared_parent() {
+        let flat = vec![
+            heading(1, "Intro"),
+            heading(2, "Install"),
+            heading(2, "Usage"),
+        ];
+
+        let roots = Generator::nest_headings(flat);
+
+        assert_eq!(roots.len(), 1);
+        assert_eq!(roots[0].title, "Intro");
+        assert_eq!(roots[0].children.len(), 2);
+        assert_eq!(roots[0].children[0].title, "Install");
+        assert_eq!(roots[0].children[1].title, "Usage");
+    }
+
+    #[test]
+    fn attaches_a_skipped_level_to_the_nearest_shallower_ancestor() {
+        // h1 -> h3 skips h2 entirely; the h3 should still nest under the h1
+        // instead of panicking or being dropped.
+        let flat = vec![heading(1, "Intro"), heading(3, "Details")];
+
+        let roots = Generator::nest_headings(flat);
+
+        assert_eq!(roots.len(), 1);
+        assert_eq!(roots[0].title, "Intro");
+        assert_eq!(roots[0].children.len(), 1);
+        assert_eq!(roots[0].children[0].title, "Details");
+        assert!(roots[0].children[0].children.is_empty());
+    }
+}
+
+fn escape_xml(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
 }
 
 pub enum GeneratorError {
@@ -199,6 +941,13 @@ pub enum GeneratorError {
         file: PathBuf,
         err: markdown_frontmatter::Error,
     },
+
+    Watch(notify::Error),
+    Serve(String),
+    UnknownTheme(String),
+    InvalidBaseUrl(String),
+    Compress(String),
+    Browserslist(String),
 }
 
 impl From<std::io::Error> for GeneratorError {
@@ -231,6 +980,12 @@ impl From<(&Path, markdown_frontmatter::Error)> for GeneratorError {
     }
 }
 
+impl From<notify::Error> for GeneratorError {
+    fn from(value: notify::Error) -> Self {
+        Self::Watch(value)
+    }
+}
+
 impl Debug for GeneratorError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -250,6 +1005,32 @@ impl Debug for GeneratorError {
                 writeln!(f, "While parsing the frontmatter from {}:", file.display())?;
                 write!(f, "{err}")
             }
+
+            Self::Watch(err) => {
+                writeln!(f, "While watching the source directory:")?;
+                write!(f, "{err}")
+            }
+
+            Self::Serve(err) => {
+                writeln!(f, "While starting the development server:")?;
+                write!(f, "{err}")
+            }
+
+            Self::UnknownTheme(name) => {
+                write!(f, "Unknown syntax highlighting theme: \"{name}\"")
+            }
+
+            Self::InvalidBaseUrl(err) => write!(f, "{err}"),
+
+            Self::Compress(err) => {
+                writeln!(f, "While compressing an output asset:")?;
+                write!(f, "{err}")
+            }
+
+            Self::Browserslist(err) => {
+                writeln!(f, "While resolving the browserslist query:")?;
+                write!(f, "{err}")
+            }
         }
     }
 }
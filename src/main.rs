@@ -1,8 +1,16 @@
-use neur::{Config, ConfigError, Generator, GeneratorError};
+use neur::{Config, ConfigError, Generator, GeneratorError, Mode};
 use std::fmt::Debug;
 
 fn main() -> Result<(), Error> {
-    Generator::new(Config::parse()?)?.run()?;
+    let config = Config::parse()?;
+    let mode = config.mode.clone();
+    let mut generator = Generator::new(config)?;
+
+    match mode {
+        Mode::Build => generator.run()?,
+        Mode::Serve { port } => generator.serve(port)?,
+    }
+
     Ok(())
 }
 